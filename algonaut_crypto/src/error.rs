@@ -15,4 +15,6 @@ pub enum CryptoError {
     InvalidWordsInMnemonic,
     #[display(fmt = "Invalid checksum.")]
     InvalidChecksum,
+    #[display(fmt = "Seed could not be used to derive a key pair.")]
+    InvalidSeed,
 }
@@ -0,0 +1,486 @@
+//! FROST (Flexible Round-Optimized Schnorr Threshold signatures) over edwards25519.
+//!
+//! A t-of-n group of enclaves can jointly produce a single, ordinary Ed25519
+//! signature over a `Transaction` without ever reconstructing the group's
+//! secret key in one place. The resulting `SignedTransaction` is
+//! indistinguishable from one signed by a plain `Account` and is far cheaper
+//! on-chain than Algorand's native multisig, which stores every subsig.
+//!
+//! Signing runs in two rounds:
+//! - round 1 (`commit`): each participant publishes hiding/binding nonce
+//!   commitments.
+//! - round 2 (`sign`): given the chosen signer set and the message, each
+//!   participant computes a partial signature, which an aggregator combines
+//!   (`aggregate`) into a single `(R, z)` Ed25519 signature.
+
+use super::transaction_id;
+use crate::error::{AlgorandError, ApiError};
+use crate::transaction::{SignedTransaction, Transaction};
+use algonaut_core::{Address, Signature};
+use curve25519_dalek::constants::ED25519_BASEPOINT_TABLE;
+use curve25519_dalek::edwards::EdwardsPoint;
+use curve25519_dalek::scalar::Scalar;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha512;
+use std::collections::BTreeMap;
+
+/// Byte encodings for the `curve25519-dalek` types used throughout this
+/// module, so round-1/round-2 state can be serialized and sent between
+/// enclaves (neither `Scalar` nor `EdwardsPoint` implements `serde::{Serialize,
+/// Deserialize}` on their own).
+mod wire {
+    use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+    use curve25519_dalek::scalar::Scalar;
+    use serde::de::Error as _;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::BTreeMap;
+
+    pub mod scalar {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(scalar: &Scalar, serializer: S) -> Result<S::Ok, S::Error> {
+            scalar.to_bytes().serialize(serializer)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Scalar, D::Error> {
+            let bytes = <[u8; 32]>::deserialize(deserializer)?;
+            Option::from(Scalar::from_canonical_bytes(bytes))
+                .ok_or_else(|| D::Error::custom("scalar is not canonical"))
+        }
+    }
+
+    pub mod point {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(
+            point: &EdwardsPoint,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            point.compress().to_bytes().serialize(serializer)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<EdwardsPoint, D::Error> {
+            let bytes = <[u8; 32]>::deserialize(deserializer)?;
+            CompressedEdwardsY(bytes)
+                .decompress()
+                .ok_or_else(|| D::Error::custom("point is not a valid edwards point"))
+        }
+    }
+
+    pub mod point_map {
+        use super::*;
+
+        pub fn serialize<S: Serializer, K: Copy + Ord + Serialize>(
+            map: &BTreeMap<K, EdwardsPoint>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            let encoded: BTreeMap<K, [u8; 32]> = map
+                .iter()
+                .map(|(id, point)| (*id, point.compress().to_bytes()))
+                .collect();
+            encoded.serialize(serializer)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>, K: Ord + Deserialize<'de>>(
+            deserializer: D,
+        ) -> Result<BTreeMap<K, EdwardsPoint>, D::Error> {
+            let encoded = BTreeMap::<K, [u8; 32]>::deserialize(deserializer)?;
+            encoded
+                .into_iter()
+                .map(|(id, bytes)| {
+                    CompressedEdwardsY(bytes)
+                        .decompress()
+                        .map(|point| (id, point))
+                        .ok_or_else(|| D::Error::custom("point is not a valid edwards point"))
+                })
+                .collect()
+        }
+    }
+}
+
+/// 1-based index of a participant within a signing group. Must match the
+/// index used when the participant's secret share was produced.
+pub type Identifier = u16;
+
+/// A participant's long-lived share of the group secret key, plus enough
+/// public material to take part in round 2. Produced once by the dealer and
+/// delivered to the owning enclave over the wire, so it is serializable like
+/// the rest of this module's round state (unlike the wire types, it must
+/// never be shared with anyone but its owner).
+#[derive(Clone, Serialize, Deserialize)]
+pub struct KeyPackage {
+    pub identifier: Identifier,
+    #[serde(with = "wire::scalar")]
+    secret_share: Scalar,
+    #[serde(with = "wire::point")]
+    group_public_key: EdwardsPoint,
+}
+
+/// Public output of key generation: the group's Algorand address, the
+/// threshold a signer set must meet, and every participant's verification
+/// share.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PublicKeyPackage {
+    #[serde(with = "wire::point")]
+    group_public_key: EdwardsPoint,
+    threshold: u16,
+    #[serde(with = "wire::point_map")]
+    pub verification_shares: BTreeMap<Identifier, EdwardsPoint>,
+}
+
+impl PublicKeyPackage {
+    /// The Algorand address a t-of-n signature from this group authorizes.
+    pub fn address(&self) -> Address {
+        Address::new(self.group_public_key.compress().to_bytes())
+    }
+}
+
+/// Split a group secret into `n` Shamir shares, any `threshold` of which can
+/// produce a signature (never the key itself). Intended for a trusted
+/// dealer; a DKG that never materializes `secret` in one place is the
+/// production-grade alternative and yields the same `KeyPackage` /
+/// `PublicKeyPackage` shapes.
+pub fn trusted_dealer_keygen(
+    secret: Scalar,
+    threshold: u16,
+    identifiers: &[Identifier],
+) -> Result<(BTreeMap<Identifier, KeyPackage>, PublicKeyPackage), AlgorandError> {
+    if threshold == 0 || (threshold as usize) > identifiers.len() {
+        return Err(ApiError::InvalidThreshold.into());
+    }
+    let mut rng = OsRng;
+    // secret-sharing polynomial f(x) = secret + c_1*x + ... + c_{t-1}*x^{t-1}
+    let mut coefficients = Vec::with_capacity(threshold as usize);
+    coefficients.push(secret);
+    for _ in 1..threshold {
+        coefficients.push(random_scalar(&mut rng));
+    }
+    let group_public_key = &coefficients[0] * &ED25519_BASEPOINT_TABLE;
+
+    let mut key_packages = BTreeMap::new();
+    let mut verification_shares = BTreeMap::new();
+    for &id in identifiers {
+        let share = evaluate_polynomial(&coefficients, id);
+        verification_shares.insert(id, &share * &ED25519_BASEPOINT_TABLE);
+        key_packages.insert(
+            id,
+            KeyPackage {
+                identifier: id,
+                secret_share: share,
+                group_public_key,
+            },
+        );
+    }
+    Ok((
+        key_packages,
+        PublicKeyPackage {
+            group_public_key,
+            threshold,
+            verification_shares,
+        },
+    ))
+}
+
+fn evaluate_polynomial(coefficients: &[Scalar], x: Identifier) -> Scalar {
+    let x = Scalar::from(x as u64);
+    let mut result = Scalar::zero();
+    for coefficient in coefficients.iter().rev() {
+        result = result * x + coefficient;
+    }
+    result
+}
+
+fn random_scalar(rng: &mut OsRng) -> Scalar {
+    let mut bytes = [0u8; 64];
+    rng.fill_bytes(&mut bytes);
+    Scalar::from_bytes_mod_order_wide(&bytes)
+}
+
+/// Lagrange coefficient `λ_i` of participant `i` over signer set `signers`.
+fn lagrange_coefficient(identifier: Identifier, signers: &[Identifier]) -> Scalar {
+    let i = Scalar::from(identifier as u64);
+    let mut num = Scalar::one();
+    let mut den = Scalar::one();
+    for &j in signers {
+        if j == identifier {
+            continue;
+        }
+        let j = Scalar::from(j as u64);
+        num *= j;
+        den *= j - i;
+    }
+    num * den.invert()
+}
+
+/// A participant's private round-1 nonce pair. Consumed by `sign`, so the
+/// type system rules out reusing a nonce pair across two signatures.
+pub struct SigningNonces {
+    hiding: Scalar,
+    binding: Scalar,
+}
+
+/// The public commitments a participant publishes in round 1. Serializable
+/// so it can travel from a signer's enclave to the aggregator's.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct SigningCommitments {
+    #[serde(with = "wire::point")]
+    hiding: EdwardsPoint,
+    #[serde(with = "wire::point")]
+    binding: EdwardsPoint,
+}
+
+/// Sample a fresh hiding/binding nonce pair and publish their commitments.
+pub fn commit() -> (SigningNonces, SigningCommitments) {
+    let mut rng = OsRng;
+    let hiding = random_scalar(&mut rng);
+    let binding = random_scalar(&mut rng);
+    (
+        SigningNonces { hiding, binding },
+        SigningCommitments {
+            hiding: &hiding * &ED25519_BASEPOINT_TABLE,
+            binding: &binding * &ED25519_BASEPOINT_TABLE,
+        },
+    )
+}
+
+/// Everything round 2 needs: the message being signed and every
+/// participating signer's round-1 commitments. Serializable so the
+/// aggregator can ship it to every signer ahead of round 2.
+#[derive(Serialize, Deserialize)]
+pub struct SigningPackage {
+    message: Vec<u8>,
+    signing_commitments: BTreeMap<Identifier, SigningCommitments>,
+}
+
+impl SigningPackage {
+    /// Build the signing package for `transaction`'s signable bytes over the
+    /// signer set implied by `signing_commitments`. Rejects a signer set
+    /// smaller than `public_key_package`'s `threshold` (`|S| ≥ t`), since
+    /// Lagrange interpolation over too few signers silently reconstructs the
+    /// wrong secret instead of erroring.
+    pub fn new(
+        transaction: &Transaction,
+        public_key_package: &PublicKeyPackage,
+        signing_commitments: BTreeMap<Identifier, SigningCommitments>,
+    ) -> Result<SigningPackage, AlgorandError> {
+        Self::from_message(
+            transaction.bytes_to_sign()?,
+            public_key_package,
+            signing_commitments,
+        )
+    }
+
+    /// As `new`, but over a raw message rather than a `Transaction`'s
+    /// signable bytes. Exposed so the signature math can be tested (and
+    /// otherwise used) independently of a `Transaction`.
+    pub fn from_message(
+        message: Vec<u8>,
+        public_key_package: &PublicKeyPackage,
+        signing_commitments: BTreeMap<Identifier, SigningCommitments>,
+    ) -> Result<SigningPackage, AlgorandError> {
+        if (signing_commitments.len() as u16) < public_key_package.threshold {
+            return Err(ApiError::InvalidThreshold.into());
+        }
+        Ok(SigningPackage {
+            message,
+            signing_commitments,
+        })
+    }
+
+    fn signers(&self) -> Vec<Identifier> {
+        self.signing_commitments.keys().copied().collect()
+    }
+
+    /// Per-signer binding factor `ρ_i = H1(i, m, B)`, `B` the encoded
+    /// commitment list.
+    fn binding_factor(&self, identifier: Identifier) -> Scalar {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(b"FROST-ed25519-binding-factor");
+        buf.extend_from_slice(&identifier.to_be_bytes());
+        buf.extend_from_slice(&self.message);
+        for (id, commitments) in &self.signing_commitments {
+            buf.extend_from_slice(&id.to_be_bytes());
+            buf.extend_from_slice(commitments.hiding.compress().as_bytes());
+            buf.extend_from_slice(commitments.binding.compress().as_bytes());
+        }
+        Scalar::hash_from_bytes::<Sha512>(&buf)
+    }
+
+    /// Group commitment `R = Σ_{j∈S} (D_j + ρ_j·E_j)`.
+    fn group_commitment(&self) -> EdwardsPoint {
+        self.signing_commitments
+            .iter()
+            .map(|(&id, commitments)| {
+                commitments.hiding + self.binding_factor(id) * commitments.binding
+            })
+            .sum()
+    }
+}
+
+/// Challenge `c = H(R‖Y‖m)`, computed the same way a plain Ed25519 signature is.
+fn challenge(r: &EdwardsPoint, group_public_key: &EdwardsPoint, message: &[u8]) -> Scalar {
+    let mut buf = Vec::new();
+    buf.extend_from_slice(r.compress().as_bytes());
+    buf.extend_from_slice(group_public_key.compress().as_bytes());
+    buf.extend_from_slice(message);
+    Scalar::hash_from_bytes::<Sha512>(&buf)
+}
+
+/// A participant's round-2 partial signature. Serializable so it can travel
+/// from a signer's enclave to the aggregator's.
+#[derive(Serialize, Deserialize)]
+pub struct SignatureShare {
+    identifier: Identifier,
+    #[serde(with = "wire::scalar")]
+    share: Scalar,
+}
+
+/// Compute this participant's partial signature
+/// `z_i = d_i + e_i·ρ_i + λ_i·s_i·c`.
+pub fn sign(
+    key_package: &KeyPackage,
+    nonces: SigningNonces,
+    signing_package: &SigningPackage,
+) -> Result<SignatureShare, AlgorandError> {
+    let signers = signing_package.signers();
+    if !signers.contains(&key_package.identifier) {
+        return Err(ApiError::InvalidSecretKeyInMultisig.into());
+    }
+    let rho = signing_package.binding_factor(key_package.identifier);
+    let r = signing_package.group_commitment();
+    let c = challenge(&r, &key_package.group_public_key, &signing_package.message);
+    let lambda = lagrange_coefficient(key_package.identifier, &signers);
+    let z = nonces.hiding + nonces.binding * rho + lambda * key_package.secret_share * c;
+    Ok(SignatureShare {
+        identifier: key_package.identifier,
+        share: z,
+    })
+}
+
+/// Combine every signer's partial signature into one Ed25519 `(R, z)` pair
+/// and wrap it in a `SignedTransaction`, ready to submit like any
+/// single-key signature.
+pub fn aggregate(
+    transaction: &Transaction,
+    signing_package: &SigningPackage,
+    signature_shares: &[SignatureShare],
+    public_key_package: &PublicKeyPackage,
+) -> Result<SignedTransaction, AlgorandError> {
+    let sig = aggregate_signature(signing_package, signature_shares, public_key_package)?;
+    Ok(SignedTransaction {
+        transaction: transaction.clone(),
+        sig: Some(sig),
+        logicsig: None,
+        multisig: None,
+        transaction_id: transaction_id(&signing_package.message),
+    })
+}
+
+/// As `aggregate`, but returns the raw Ed25519 `(R, z)` signature rather than
+/// a `SignedTransaction`. Split out so the signature math can be tested (and
+/// otherwise used) independently of a `Transaction`.
+fn aggregate_signature(
+    signing_package: &SigningPackage,
+    signature_shares: &[SignatureShare],
+    public_key_package: &PublicKeyPackage,
+) -> Result<Signature, AlgorandError> {
+    let signers = signing_package.signers();
+    if (signers.len() as u16) < public_key_package.threshold {
+        return Err(ApiError::InvalidThreshold.into());
+    }
+    if signature_shares.len() != signers.len()
+        || signature_shares
+            .iter()
+            .any(|share| !signers.contains(&share.identifier))
+    {
+        return Err(ApiError::InvalidNumberOfSubsignatures.into());
+    }
+    let r = signing_package.group_commitment();
+    let z: Scalar = signature_shares.iter().map(|share| share.share).sum();
+
+    // Verify the combined signature against the group's public key before
+    // handing it back, so a faulty or malicious signer can't produce a
+    // `SignedTransaction` that only fails once it's submitted to `algod`.
+    let c = challenge(
+        &r,
+        &public_key_package.group_public_key,
+        &signing_package.message,
+    );
+    if &z * &ED25519_BASEPOINT_TABLE != r + c * public_key_package.group_public_key {
+        return Err(ApiError::MismatchingSignatures.into());
+    }
+
+    let mut sig_bytes = [0u8; 64];
+    sig_bytes[..32].copy_from_slice(r.compress().as_bytes());
+    sig_bytes[32..].copy_from_slice(z.as_bytes());
+    Ok(Signature(sig_bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::signature::{UnparsedPublicKey, ED25519};
+
+    fn keygen(
+        threshold: u16,
+        identifiers: &[Identifier],
+    ) -> (BTreeMap<Identifier, KeyPackage>, PublicKeyPackage) {
+        let mut rng = OsRng;
+        let secret = random_scalar(&mut rng);
+        trusted_dealer_keygen(secret, threshold, identifiers).unwrap()
+    }
+
+    #[test]
+    fn round_trip_produces_an_algod_valid_signature() {
+        let identifiers: Vec<Identifier> = vec![1, 2, 3];
+        let (key_packages, public_key_package) = keygen(2, &identifiers);
+        let signers: Vec<Identifier> = vec![1, 3];
+        let message = b"hello algorand".to_vec();
+
+        let mut nonces = BTreeMap::new();
+        let mut commitments = BTreeMap::new();
+        for &id in &signers {
+            let (n, c) = commit();
+            nonces.insert(id, n);
+            commitments.insert(id, c);
+        }
+
+        let signing_package =
+            SigningPackage::from_message(message.clone(), &public_key_package, commitments)
+                .unwrap();
+
+        let shares: Vec<SignatureShare> = signers
+            .iter()
+            .map(|id| {
+                let key_package = key_packages.get(id).unwrap();
+                let nonce = nonces.remove(id).unwrap();
+                sign(key_package, nonce, &signing_package).unwrap()
+            })
+            .collect();
+
+        let signature = aggregate_signature(&signing_package, &shares, &public_key_package)
+            .expect("aggregation of a valid quorum should succeed");
+
+        let address = public_key_package.address();
+        UnparsedPublicKey::new(&ED25519, &address.0)
+            .verify(&message, &signature.0)
+            .expect("FROST signature should verify as an ordinary Ed25519 signature");
+    }
+
+    #[test]
+    fn signing_package_rejects_a_signer_set_below_threshold() {
+        let identifiers: Vec<Identifier> = vec![1, 2, 3];
+        let (_key_packages, public_key_package) = keygen(2, &identifiers);
+        let message = b"hello algorand".to_vec();
+
+        let (_nonce, commitments) = commit();
+        let mut one_signer = BTreeMap::new();
+        one_signer.insert(1u16, commitments);
+
+        assert!(SigningPackage::from_message(message, &public_key_package, one_signer).is_err());
+    }
+}
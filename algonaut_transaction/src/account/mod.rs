@@ -5,6 +5,7 @@ use algonaut_core::{
     Address, LogicSignature, MultisigAddress, MultisigSignature, MultisigSubsig, Signature,
     ToMsgPack,
 };
+use algonaut_crypto::error::CryptoError;
 use algonaut_crypto::mnemonic;
 use algonaut_crypto::Ed25519PublicKey;
 use data_encoding::BASE32_NOPAD;
@@ -12,11 +13,29 @@ use rand::rngs::OsRng;
 use rand::Rng;
 use ring::signature::Ed25519KeyPair as KeyPairType;
 use ring::signature::KeyPair;
+#[cfg(feature = "experimental_signing")]
+use curve25519_dalek::scalar::Scalar;
 use sha2::Digest;
+#[cfg(feature = "experimental_signing")]
+use sha2::Sha512;
 use std::borrow::Borrow;
 
+// These are newer, less battle-tested signing paths compared to plain and
+// multisig accounts; gate them so callers opt in explicitly.
+#[cfg(feature = "experimental_signing")]
+pub mod frost;
+#[cfg(feature = "experimental_signing")]
+pub mod musig2;
+
 type ChecksumAlg = sha2::Sha512Trunc256;
 
+/// Algorand's transaction id: the base32 encoding of the checksum of the
+/// signable bytes. Shared by every signing path (single-key, multisig,
+/// threshold) so a transaction's id never depends on who signed it.
+pub(crate) fn transaction_id(bytes_to_sign: &[u8]) -> String {
+    BASE32_NOPAD.encode(&ChecksumAlg::digest(bytes_to_sign))
+}
+
 pub struct Account {
     seed: [u8; 32],
     address: Address,
@@ -24,7 +43,7 @@ pub struct Account {
 }
 
 impl Account {
-    pub fn generate() -> Account {
+    pub fn generate() -> Result<Account, AlgorandError> {
         let seed: [u8; 32] = OsRng.gen();
         Self::from_seed(seed)
     }
@@ -32,20 +51,21 @@ impl Account {
     /// Create account from human readable mnemonic of a 32 byte seed
     pub fn from_mnemonic(mnemonic: &str) -> Result<Account, AlgorandError> {
         let seed = mnemonic::to_key(mnemonic)?;
-        Ok(Self::from_seed(seed))
+        Self::from_seed(seed)
     }
 
     /// Create account from 32 byte seed
-    pub fn from_seed(seed: [u8; 32]) -> Account {
-        let key_pair = KeyPairType::from_seed_unchecked(&seed).unwrap();
+    pub fn from_seed(seed: [u8; 32]) -> Result<Account, AlgorandError> {
+        let key_pair = KeyPairType::from_seed_unchecked(&seed)
+            .map_err(|_| CryptoError::InvalidSeed)?;
         let mut pk = [0; 32];
         pk.copy_from_slice(key_pair.public_key().as_ref());
         let address = Address::new(pk);
-        Account {
+        Ok(Account {
             seed,
             address,
             key_pair,
-        }
+        })
     }
 
     /// Get the public key address of the account
@@ -54,8 +74,8 @@ impl Account {
     }
 
     /// Get the human readable mnemonic of the 32 byte seed
-    pub fn mnemonic(&self) -> String {
-        mnemonic::from_key(&self.seed).unwrap()
+    pub fn mnemonic(&self) -> Result<String, AlgorandError> {
+        Ok(mnemonic::from_key(&self.seed)?)
     }
 
     /// Get the 32 byte seed
@@ -63,6 +83,21 @@ impl Account {
         self.seed
     }
 
+    /// Expanded Ed25519 private scalar `x`, derived the same way `ring`
+    /// derives it internally (clamped low 32 bytes of `SHA-512(seed)`).
+    /// Exposed so signing schemes that need to combine scalars directly
+    /// (see `musig2`) can do so without reimplementing key expansion.
+    #[cfg(feature = "experimental_signing")]
+    pub(crate) fn secret_scalar(&self) -> Scalar {
+        let hash = Sha512::digest(&self.seed);
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(&hash[..32]);
+        bytes[0] &= 248;
+        bytes[31] &= 63;
+        bytes[31] |= 64;
+        Scalar::from_bits(bytes)
+    }
+
     fn sign(&self, bytes: &[u8]) -> Signature {
         let signature = self.key_pair.sign(&bytes);
         // ring returns a signature with padding at the end to make it 105 bytes, only 64 bytes are actually used
@@ -94,7 +129,7 @@ impl Account {
     ) -> Result<SignedTransaction, AlgorandError> {
         let transaction_bytes = &transaction.bytes_to_sign()?;
         let signature = self.sign(&transaction_bytes);
-        let id = BASE32_NOPAD.encode(&ChecksumAlg::digest(&transaction.bytes_to_sign()?));
+        let id = transaction_id(transaction_bytes);
         Ok(SignedTransaction {
             transaction: transaction.clone(),
             sig: Some(signature),
@@ -241,12 +276,18 @@ impl Account {
         }
         let mut merged = transactions[0].borrow().clone();
         for transaction in transactions {
-            let merged_msig = merged.multisig.as_mut().unwrap();
-            let msig = transaction.borrow().multisig.as_ref().unwrap();
+            let merged_msig = merged
+                .multisig
+                .as_mut()
+                .ok_or(ApiError::MissingMultisig)?;
+            let msig = transaction
+                .borrow()
+                .multisig
+                .as_ref()
+                .ok_or(ApiError::MissingMultisig)?;
             if merged_msig.subsigs.len() != msig.subsigs.len() {
                 return Err(ApiError::InvalidNumberOfSubsignatures.into());
             }
-            assert_eq!(merged_msig.subsigs.len(), msig.subsigs.len());
             for (merged_subsig, subsig) in merged_msig.subsigs.iter_mut().zip(&msig.subsigs) {
                 if subsig.key != merged_subsig.key {
                     return Err(ApiError::InvalidPublicKeyInMultisig.into());
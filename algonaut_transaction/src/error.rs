@@ -0,0 +1,41 @@
+use std::prelude::v1::*;
+
+extern crate derive_more;
+use algonaut_crypto::error::CryptoError;
+use derive_more::{Display, From};
+use std::fmt::Debug;
+use thiserror::Error;
+
+/// Top level error type returned by this crate.
+#[derive(Debug, Display, Error, From, Clone)]
+pub enum AlgorandError {
+    #[display(fmt = "{}", _0)]
+    Api(ApiError),
+    #[display(fmt = "{}", _0)]
+    Crypto(CryptoError),
+}
+
+/// Errors raised while building, signing, or combining transactions and accounts.
+#[derive(Debug, Display, Error, From, Clone)]
+pub enum ApiError {
+    #[display(fmt = "Secret key does not belong to the multisig account.")]
+    InvalidSecretKeyInMultisig,
+    #[display(fmt = "Transaction sender does not match the multisig account.")]
+    InvalidSenderInMultisig,
+    #[display(fmt = "At least two transactions are required to merge multisig signatures.")]
+    InsufficientTransactions,
+    #[display(fmt = "Transactions to merge have differing numbers of subsignatures.")]
+    InvalidNumberOfSubsignatures,
+    #[display(fmt = "Subsignature key does not match the multisig account.")]
+    InvalidPublicKeyInMultisig,
+    #[display(fmt = "Subsignatures for the same key do not match.")]
+    MismatchingSignatures,
+    #[display(fmt = "Transaction is missing a multisig to merge into.")]
+    MissingMultisig,
+    #[display(fmt = "Signer set does not meet the threshold required to sign.")]
+    InvalidThreshold,
+    #[display(fmt = "Signature is invalid for the given message and key.")]
+    InvalidSignature,
+    #[display(fmt = "At least two public keys are required to aggregate a key.")]
+    InsufficientKeys,
+}
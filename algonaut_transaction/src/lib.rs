@@ -8,6 +8,7 @@ pub mod auction;
 pub mod builder;
 pub mod error;
 pub mod transaction;
+pub mod verify;
 
 pub use builder::{
     AcceptAsset, CallApplication, ClawbackAsset, ConfigureAsset, FreezeAsset, Pay, RegisterKey,
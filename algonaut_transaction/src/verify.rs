@@ -0,0 +1,154 @@
+//! Verification for transactions produced (or merged) by this crate, so a
+//! caller can check a signature, or that a merged multisig satisfies its
+//! threshold, before handing it to `algod`.
+//!
+//! `Address` is defined in `algonaut_core`, a separate crate from this one,
+//! and Rust only allows inherent impls on a type from within its own crate —
+//! so an `impl Address { fn verify }` here, the way the non-SGX crate does
+//! it, isn't legal. The standalone signature check is exposed as the free
+//! function [`verify`] instead.
+
+use crate::error::{AlgorandError, ApiError};
+use crate::transaction::SignedTransaction;
+use algonaut_core::{Address, MultisigAddress, MultisigSignature, Signature};
+use algonaut_crypto::Ed25519PublicKey;
+use ring::signature::{UnparsedPublicKey, ED25519};
+
+impl SignedTransaction {
+    /// Verify that this transaction carries a valid signature for its
+    /// sender: a single Ed25519 `sig`, or, for a multisig transaction,
+    /// enough valid subsigs to meet the declared threshold. Recomputes
+    /// `bytes_to_sign()` rather than trusting the cached `transaction_id`.
+    ///
+    /// Note: if the sender account has been rekeyed, the signature is
+    /// expected under its current auth address; this method only checks
+    /// against `transaction.sender` and leaves auth-address lookups (which
+    /// require network state) to the caller.
+    pub fn verify(&self) -> Result<bool, AlgorandError> {
+        let bytes = self.transaction.bytes_to_sign()?;
+        if let Some(multisig) = &self.multisig {
+            return verify_multisig(self.transaction.sender, &bytes, multisig);
+        }
+        if let Some(sig) = &self.sig {
+            let sender_key = Ed25519PublicKey(self.transaction.sender.0);
+            return Ok(verify(&sender_key, &bytes, sig).is_ok());
+        }
+        Ok(false)
+    }
+}
+
+/// As the `multisig` branch of `SignedTransaction::verify`, but taking the
+/// sender directly so it can be exercised without constructing a
+/// `Transaction`.
+fn verify_multisig(
+    sender: Address,
+    bytes: &[u8],
+    multisig: &MultisigSignature,
+) -> Result<bool, AlgorandError> {
+    let keys: Vec<Ed25519PublicKey> = multisig.subsigs.iter().map(|subsig| subsig.key).collect();
+    let declared = MultisigAddress::new(multisig.version, multisig.threshold, &keys)
+        .map_err(|_| ApiError::InvalidPublicKeyInMultisig)?;
+    if declared.address() != sender {
+        return Err(ApiError::InvalidSenderInMultisig.into());
+    }
+
+    let mut valid_subsigs = 0u8;
+    for subsig in &multisig.subsigs {
+        if let Some(sig) = subsig.sig {
+            if verify(&subsig.key, bytes, &sig).is_ok() {
+                valid_subsigs += 1;
+            }
+        }
+    }
+    Ok(valid_subsigs >= multisig.threshold)
+}
+
+/// Verify a standalone Ed25519 signature against a public key. Equivalent
+/// to the non-SGX crate's `Address::verify(msg, sig)`.
+pub fn verify(
+    public_key: &Ed25519PublicKey,
+    msg: &[u8],
+    sig: &Signature,
+) -> Result<(), AlgorandError> {
+    UnparsedPublicKey::new(&ED25519, &public_key.0)
+        .verify(msg, &sig.0)
+        .map_err(|_| ApiError::InvalidSignature.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use algonaut_core::MultisigSubsig;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    fn keypair() -> (Ed25519KeyPair, Ed25519PublicKey) {
+        let rng = ring::rand::SystemRandom::new();
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let mut public_key = [0u8; 32];
+        public_key.copy_from_slice(key_pair.public_key().as_ref());
+        (key_pair, Ed25519PublicKey(public_key))
+    }
+
+    fn sign_raw(key_pair: &Ed25519KeyPair, msg: &[u8]) -> Signature {
+        let signature = key_pair.sign(msg);
+        let mut bytes = [0u8; 64];
+        bytes.copy_from_slice(&signature.as_ref()[..64]);
+        Signature(bytes)
+    }
+
+    fn multisig_of(
+        signers: &[(Ed25519KeyPair, Ed25519PublicKey)],
+        threshold: u8,
+        msg: &[u8],
+        signing: usize,
+    ) -> (Address, MultisigSignature) {
+        let keys: Vec<Ed25519PublicKey> = signers.iter().map(|(_, key)| *key).collect();
+        let sender = MultisigAddress::new(1, threshold, &keys).unwrap().address();
+        let subsigs = signers
+            .iter()
+            .enumerate()
+            .map(|(i, (key_pair, key))| MultisigSubsig {
+                key: *key,
+                sig: if i < signing {
+                    Some(sign_raw(key_pair, msg))
+                } else {
+                    None
+                },
+            })
+            .collect();
+        (
+            sender,
+            MultisigSignature {
+                version: 1,
+                threshold,
+                subsigs,
+            },
+        )
+    }
+
+    #[test]
+    fn multisig_at_threshold_is_accepted() {
+        let msg = b"hello algorand";
+        let signers = vec![keypair(), keypair(), keypair()];
+        let (sender, multisig) = multisig_of(&signers, 2, msg, 2);
+        assert!(verify_multisig(sender, msg, &multisig).unwrap());
+    }
+
+    #[test]
+    fn multisig_under_threshold_is_rejected() {
+        let msg = b"hello algorand";
+        let signers = vec![keypair(), keypair(), keypair()];
+        let (sender, multisig) = multisig_of(&signers, 2, msg, 1);
+        assert!(!verify_multisig(sender, msg, &multisig).unwrap());
+    }
+
+    #[test]
+    fn multisig_with_wrong_sender_is_rejected() {
+        let msg = b"hello algorand";
+        let signers = vec![keypair(), keypair(), keypair()];
+        let (_, multisig) = multisig_of(&signers, 2, msg, 2);
+        let wrong_sender = Address::new([0u8; 32]);
+        assert!(verify_multisig(wrong_sender, msg, &multisig).is_err());
+    }
+}